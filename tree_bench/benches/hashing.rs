@@ -2,12 +2,15 @@ use aptos_crypto::HashValue;
 use aptos_experimental_hexy::in_mem::base::HexyBase;
 use aptos_experimental_hexy::in_mem::overlay::HexyOverlay;
 use aptos_experimental_hexy::LeafIdx;
+use aptos_experimental_hexy::ARITY;
 use criterion::measurement::WallTime;
 use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkGroup, Criterion};
 use fastcrypto::hash::{EllipticCurveMultisetHash, MultisetHash};
 use rand::Rng;
 use rayon::prelude::*;
+use std::hint::black_box;
 use std::sync::Arc;
+use std::time::Instant;
 
 fn inc_hash(c: &mut Criterion) {
     let mut group = c.benchmark_group("inc_hashing");
@@ -98,6 +101,72 @@ fn inc_hash_parallel(c: &mut Criterion) {
     });
 }
 
+/// Checks that `commitment` is the multiset hash of `residual`'s multiset plus one more copy
+/// of `element`, i.e. that removing `element` from `commitment` leaves `residual`.
+///
+/// The EC multiset hash is order independent and collision resistant over multisets, so when
+/// both `commitment` and `residual` are trusted on their own (e.g. both published by the same
+/// party), a passing check shows `element` is in the committed multiset. Given `commitment`
+/// alone it proves nothing: anyone can make a residual for any element by removing it. It
+/// also says nothing about the element's position or multiplicity, so it is no replacement
+/// for a Merkle proof.
+fn verify_multiset_membership(
+    commitment: &EllipticCurveMultisetHash,
+    element: &HashValue,
+    residual: &EllipticCurveMultisetHash,
+) -> bool {
+    let mut expected = residual.clone();
+    expected.insert(element.as_slice());
+    expected == *commitment
+}
+
+fn inc_hash_membership(c: &mut Criterion) {
+    let mut group = c.benchmark_group("inc_hashing_membership");
+
+    const SET_SIZE: usize = 10000;
+    const NUM_CHECKS: usize = 1000;
+
+    let elements: Vec<_> = (0..SET_SIZE).map(|_| HashValue::random()).collect();
+    let mut commitment = EllipticCurveMultisetHash::default();
+    for element in &elements {
+        commitment.insert(element.as_slice());
+    }
+
+    let claims: Vec<_> = elements[..NUM_CHECKS]
+        .iter()
+        .map(|element| {
+            let mut residual = commitment.clone();
+            residual.remove(element.as_slice());
+            (*element, residual)
+        })
+        .collect();
+
+    // Removing an element must agree with hashing the rest of the set from scratch.
+    let mut rest = EllipticCurveMultisetHash::default();
+    for element in &elements[1..] {
+        rest.insert(element.as_slice());
+    }
+    assert!(verify_multiset_membership(&commitment, &elements[0], &rest));
+    assert!(claims
+        .iter()
+        .all(|(element, residual)| verify_multiset_membership(&commitment, element, residual)));
+    assert!(!verify_multiset_membership(
+        &commitment,
+        &HashValue::random(),
+        &rest
+    ));
+
+    group.throughput(criterion::Throughput::Elements(NUM_CHECKS as u64));
+
+    group.bench_function("verify_membership", |b| {
+        b.iter(|| {
+            for (element, residual) in &claims {
+                black_box(verify_multiset_membership(&commitment, element, residual));
+            }
+        })
+    });
+}
+
 fn n_parent_nodes(arity: usize, n_nodes: usize) -> usize {
     n_nodes / arity + (n_nodes % arity != 0) as usize
 }
@@ -195,6 +264,111 @@ fn complete_merkle_tree_sims(c: &mut Criterion) {
     }
 }
 
+/// Salts carry the arity, so roots can't collide across arities. Nodes right above the leaves
+/// also get their own salt, separate from higher internal nodes, so a tree over leaves can't be
+/// confused with a tree over leaf-parent hashes. Short chunks at the end of a level are hashed
+/// as is (no padding to `arity`), consistent with `n_parent_nodes`.
+fn multi_arity_salt(arity: usize, leaf_parent: bool) -> String {
+    let kind = if leaf_parent {
+        "leaf_parent"
+    } else {
+        "internal"
+    };
+    format!("multi_arity_root_arity_{arity}_{kind}")
+}
+
+fn hash_level(nodes: &[HashValue], salt: &str, arity: usize) -> Vec<HashValue> {
+    nodes
+        .chunks(arity)
+        .map(|children| {
+            let mut hasher = aptos_crypto::hash::DefaultHasher::new(salt.as_bytes());
+            for hash in children {
+                hasher.update(hash.as_ref());
+            }
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// Root over `leaves` with the given arity. Leaves are always hashed by at least one
+/// leaf-parent node, so a single leaf yields `H_leaf_parent(leaf)`, not the leaf itself. An
+/// empty leaf set yields `HashValue::zero()` as a placeholder root.
+fn multi_arity_root(leaves: &[HashValue], arity: usize) -> HashValue {
+    assert!(arity >= 2, "arity must be at least 2, got {arity}");
+
+    if leaves.is_empty() {
+        return HashValue::zero();
+    }
+
+    let mut level = hash_level(leaves, &multi_arity_salt(arity, true), arity);
+    let internal_salt = multi_arity_salt(arity, false);
+    while level.len() > 1 {
+        level = hash_level(&level, &internal_salt, arity);
+    }
+    level[0]
+}
+
+/// Real roots over the same leaves, one per arity, computed in parallel across arities.
+fn multi_arity_roots(leaves: &[HashValue], arities: &[usize]) -> Vec<(usize, HashValue)> {
+    arities
+        .par_iter()
+        .map(|&arity| (arity, multi_arity_root(leaves, arity)))
+        .collect()
+}
+
+fn check_multi_arity_root() {
+    let hash = |salt: String, children: &[HashValue]| {
+        let mut hasher = aptos_crypto::hash::DefaultHasher::new(salt.as_bytes());
+        for child in children {
+            hasher.update(child.as_ref());
+        }
+        hasher.finish()
+    };
+
+    let [a, b, c, d] = [(); 4].map(|_| HashValue::random());
+
+    let ab = hash(multi_arity_salt(2, true), &[a, b]);
+    let cd = hash(multi_arity_salt(2, true), &[c, d]);
+    let expected = hash(multi_arity_salt(2, false), &[ab, cd]);
+    assert_eq!(multi_arity_root(&[a, b, c, d], 2), expected);
+
+    assert_eq!(multi_arity_root(&[], 2), HashValue::zero());
+    assert_eq!(
+        multi_arity_root(&[a], 2),
+        hash(multi_arity_salt(2, true), &[a])
+    );
+    assert_ne!(multi_arity_root(&[a, b], 2), multi_arity_root(&[a, b], 4));
+    assert_ne!(
+        multi_arity_root(&[a, b, c, d], 2),
+        multi_arity_root(&[ab, cd], 2)
+    );
+}
+
+fn multi_arity_roots_bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multi_arity_roots");
+    group.sample_size(10);
+
+    const M: usize = 1024 * 1024;
+    const ARITIES: [usize; 7] = [2, 4, 8, 16, 32, 64, 256];
+
+    check_multi_arity_root();
+
+    for set_size_m in [1, 16] {
+        println!("Generating {set_size_m}M leaves");
+        let leaves: Vec<_> = (0..set_size_m * M)
+            .into_par_iter()
+            .with_min_len(1024)
+            .map(|_| HashValue::random())
+            .collect();
+
+        group.throughput(criterion::Throughput::Elements(
+            (set_size_m * M * ARITIES.len()) as u64,
+        ));
+        let name = format!("leaves_{set_size_m}m_arities_{}", ARITIES.len());
+        group.bench_function(&name, |b| b.iter(|| multi_arity_roots(&leaves, &ARITIES)));
+    }
+}
+
 fn gen_hexy_updates(batch_size_k: usize, set_size_m: usize) -> Vec<(LeafIdx, HashValue)> {
     const M: usize = 1024 * 1024;
     const K: usize = 1024;
@@ -209,12 +383,11 @@ fn gen_hexy_updates(batch_size_k: usize, set_size_m: usize) -> Vec<(LeafIdx, Has
         .collect()
 }
 
-fn hexy_update(
-    group: &mut BenchmarkGroup<WallTime>,
+fn prepare_hexy_pipeline(
     batch_size_k: usize,
     set_size_m: usize,
     pipeline_depth: usize,
-) {
+) -> (Arc<HexyBase>, HexyOverlay, HexyOverlay) {
     const M: usize = 1024 * 1024;
 
     println!("Allocating base: {set_size_m}M items");
@@ -229,13 +402,73 @@ fn hexy_update(
             .new_overlay(updates)
             .unwrap();
     }
+
+    (base, root_overlay, base_overlay)
+}
+
+/// Thousands of updates per second a batch would reach if it cost only its modeled hashing:
+/// `num_hashing_per_batch` nodes of `arity` children each, single threaded like
+/// `complete_merkle_tree_sim`.
+fn modeled_k_updates_per_sec(
+    arity: usize,
+    num_hashing_per_batch: usize,
+    batch_size_k: usize,
+) -> f64 {
+    const ROUNDS: usize = 5;
+
+    let siblings: Vec<_> = (0..arity).map(|_| HashValue::random()).collect();
+    let fastest = (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..num_hashing_per_batch {
+                let mut hasher = aptos_crypto::hash::DefaultHasher::new(b"hexy_model");
+                for hash in &siblings {
+                    hasher.update(hash.as_ref());
+                }
+                black_box(hasher.finish());
+            }
+            start.elapsed()
+        })
+        .min()
+        .unwrap();
+
+    (batch_size_k * 1024) as f64 / 1000.0 / fastest.as_secs_f64()
+}
+
+fn hexy_update(
+    group: &mut BenchmarkGroup<WallTime>,
+    batch_size_k: usize,
+    set_size_m: usize,
+    pipeline_depth: usize,
+) {
+    const M: usize = 1024 * 1024;
+    const K: usize = 1024;
+
+    let (base, root_overlay, base_overlay) =
+        prepare_hexy_pipeline(batch_size_k, set_size_m, pipeline_depth);
     let updates = gen_hexy_updates(batch_size_k, set_size_m);
 
-    group.throughput(criterion::Throughput::Elements(batch_size_k as u64 * 1024));
     let name = format!(
         "hexy_update_leaves_{}m_batch_{}k_pipeline_depth_{}",
         set_size_m, batch_size_k, pipeline_depth
     );
+
+    // Modeled numbers for this exact update, paired by to_csv.py with the measured throughput
+    // below.
+    let num_hashing_per_batch =
+        num_complete_tree_internal_nodes_to_update(ARITY, set_size_m * M, batch_size_k * K);
+    let modeled_k_updates_per_sec =
+        modeled_k_updates_per_sec(ARITY, num_hashing_per_batch, batch_size_k);
+    print!("{{\"name\": \"{name}\", ");
+    print!("\"set_size_m\": {set_size_m}, ");
+    print!("\"batch_size_k\": {batch_size_k}, ");
+    print!("\"arity\": {ARITY}, ");
+    print!("\"pipeline_depth\": {pipeline_depth}, ");
+    print!("\"num_hashing_per_batch\": {num_hashing_per_batch}, ");
+    print!("\"modeled_k_updates_per_sec\": {modeled_k_updates_per_sec:.3}");
+    print!("}}\n\n\n");
+
+    group.throughput(criterion::Throughput::Elements(batch_size_k as u64 * 1024));
     group.bench_function(&name, |b| {
         b.iter_batched(
             || updates.clone(),
@@ -262,10 +495,50 @@ fn hexy_updates(c: &mut Criterion) {
     }
 }
 
+fn hexy_read(
+    group: &mut BenchmarkGroup<WallTime>,
+    batch_size_k: usize,
+    set_size_m: usize,
+    pipeline_depth: usize,
+) {
+    const M: usize = 1024 * 1024;
+    const NUM_READS: usize = 10_000;
+
+    let (base, root_overlay, base_overlay) =
+        prepare_hexy_pipeline(batch_size_k, set_size_m, pipeline_depth);
+    let view = base_overlay.view(&base, &root_overlay);
+    let indices: Vec<LeafIdx> = (0..NUM_READS)
+        .map(|_| rand::thread_rng().gen_range(0..(set_size_m * M) as u32))
+        .collect();
+
+    group.throughput(criterion::Throughput::Elements(NUM_READS as u64));
+    let name = format!(
+        "hexy_read_leaves_{}m_batch_{}k_pipeline_depth_{}",
+        set_size_m, batch_size_k, pipeline_depth
+    );
+    group.bench_function(&name, |b| {
+        b.iter(|| {
+            for idx in &indices {
+                black_box(view.get_leaf(*idx));
+            }
+        })
+    });
+}
+
+fn hexy_reads(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("hexy_reads"));
+
+    // Reads at depth 0 go straight to the base; deeper chains show the cost of consulting
+    // more overlay layers on the way down.
+    for pipeline_depth in [0, 2, 8, 32] {
+        hexy_read(&mut group, 10, 32, pipeline_depth);
+    }
+}
+
 criterion_group!(
     name = hashing;
     config = Criterion::default();
-    targets = inc_hash, inc_hash_parallel, hexy_updates, complete_merkle_tree_sims
+    targets = inc_hash, inc_hash_parallel, inc_hash_membership, hexy_updates, hexy_reads, complete_merkle_tree_sims, multi_arity_roots_bench
 );
 
 criterion_main!(hashing);
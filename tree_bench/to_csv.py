@@ -17,7 +17,10 @@ FIELD_NAMES = [
     'total_memory_m',
     'num_hashing_per_batch',
     'disk_bytes_per_update',
+    'pipeline_depth',
+    'modeled_k_updates_per_sec',
     'k_updates_per_sec',
+    'real_vs_modeled',
 ]
 writer = csv.DictWriter(sys.stdout, fieldnames=FIELD_NAMES)
 writer.writeheader()
@@ -26,6 +29,9 @@ writer.writeheader()
 record = None
 for line in sys.stdin:
     if record is None:
+        if "thrpt:" in line:
+            # a bench that prints no parameter record (e.g. hexy_reads), nothing to pair with
+            continue
         line = re.sub(r"(\w+):", '"\\1":', line)
         line = line.replace("'", '"')
         record = json.loads(line)
@@ -34,5 +40,7 @@ for line in sys.stdin:
         if not m:
             raise "Didn't match"
         record['k_updates_per_sec'] = float(m.group(1))
+        if 'modeled_k_updates_per_sec' in record:
+            record['real_vs_modeled'] = record['k_updates_per_sec'] / record['modeled_k_updates_per_sec']
         writer.writerow(record)
         record = None
\ No newline at end of file